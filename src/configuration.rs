@@ -1,38 +1,112 @@
-use config::{Config, ConfigError, File};
+use crate::email_client::EmailClientSettings;
+use config::{Config, ConfigError, Environment as ConfigEnvironment, File};
+use serde_aux::field_attributes::deserialize_number_from_string;
+use sqlx::postgres::{PgConnectOptions, PgSslMode};
+
 #[derive(serde::Deserialize)]
 pub struct Setting {
     pub database: DatabaseSettings,
-    pub application_port: u16,
+    pub application: ApplicationSettings,
+    pub email_client: EmailClientSettings,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ApplicationSettings {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub port: u16,
+    pub host: String,
+    pub base_url: String,
 }
 
+/// Reads `configuration/base.yaml`, layers the environment-specific file
+/// selected by `APP_ENVIRONMENT` (`local` by default) on top of it, then
+/// overlays any `APP__`-prefixed environment variables so secrets can be
+/// injected at deploy time without touching the checked-in files.
 pub fn get_configuration() -> Result<Setting, ConfigError> {
-    let mut settings = Config::default();
-    settings.merge(File::with_name("configuration"))?;
-    settings.try_into()
+    let base_path = std::env::current_dir().expect("Failed to determine the current directory");
+    let configuration_directory = base_path.join("configuration");
+
+    let environment: Environment = std::env::var("APP_ENVIRONMENT")
+        .unwrap_or_else(|_| "local".into())
+        .try_into()
+        .expect("Failed to parse APP_ENVIRONMENT.");
+    let environment_filename = format!("{}.yaml", environment.as_str());
+
+    let settings = Config::builder()
+        .add_source(File::from(configuration_directory.join("base.yaml")))
+        .add_source(File::from(configuration_directory.join(environment_filename)))
+        .add_source(
+            ConfigEnvironment::with_prefix("APP")
+                .prefix_separator("__")
+                .separator("__"),
+        )
+        .build()?;
+
+    settings.try_deserialize::<Setting>()
 }
 
-#[derive(serde::Deserialize)]
+/// The two environments the application is deployed to. Selected via the
+/// `APP_ENVIRONMENT` variable, defaulting to `local`.
+pub enum Environment {
+    Local,
+    Production,
+}
+
+impl Environment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Local => "local",
+            Environment::Production => "production",
+        }
+    }
+}
+
+impl TryFrom<String> for Environment {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "production" => Ok(Self::Production),
+            other => Err(format!(
+                "{} is not a supported environment. Use either `local` or `production`.",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Clone)]
 pub struct DatabaseSettings {
     pub username: String,
     pub password: String,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
     pub port: u16,
     pub host: String,
     pub database_name: String,
+    pub require_ssl: bool,
 }
 
 impl DatabaseSettings {
-    /// Returns the connection string for the stored database
-    pub fn get_connection_string(&self) -> String {
-        format!(
-            "postgres://{}:{}@{}:{}/{}",
-            self.username, self.password, self.host, self.port, self.database_name
-        )
+    /// Connection options for the Postgres server, without selecting a
+    /// database. Used to create/drop the per-test database.
+    pub fn without_db(&self) -> PgConnectOptions {
+        let ssl_mode = if self.require_ssl {
+            PgSslMode::Require
+        } else {
+            PgSslMode::Prefer
+        };
+        PgConnectOptions::new()
+            .host(&self.host)
+            .username(&self.username)
+            .password(&self.password)
+            .port(self.port)
+            .ssl_mode(ssl_mode)
     }
-    /// Returns the connection string for the default database
-    pub fn get_connection_string_default_db(&self) -> String {
-        format!(
-            "postgres://{}:{}@{}:{}",
-            self.username, self.password, self.host, self.port
-        )
+
+    /// Connection options for the Postgres server, with `database_name`
+    /// selected. Used by the application pool.
+    pub fn with_db(&self) -> PgConnectOptions {
+        self.without_db().database(&self.database_name)
     }
 }
@@ -0,0 +1,54 @@
+use validator::validate_email;
+
+/// A subscriber's email address, validated for basic RFC-ish structure
+/// before it ever reaches the database or the email client.
+#[derive(Debug)]
+pub struct SubscriberEmail(String);
+
+impl TryFrom<String> for SubscriberEmail {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if validate_email(&s) {
+            Ok(Self(s))
+        } else {
+            Err(format!("{} is not a valid subscriber email.", s))
+        }
+    }
+}
+
+impl AsRef<str> for SubscriberEmail {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubscriberEmail;
+    use claims::assert_err;
+
+    #[test]
+    fn empty_string_is_rejected() {
+        let email = "".to_string();
+        assert_err!(SubscriberEmail::try_from(email));
+    }
+
+    #[test]
+    fn email_missing_at_symbol_is_rejected() {
+        let email = "ursuladomain.com".to_string();
+        assert_err!(SubscriberEmail::try_from(email));
+    }
+
+    #[test]
+    fn email_missing_subject_is_rejected() {
+        let email = "@domain.com".to_string();
+        assert_err!(SubscriberEmail::try_from(email));
+    }
+
+    #[test]
+    fn valid_emails_are_parsed_successfully() {
+        let email = "ursula_le_guin@gmail.com".to_string();
+        assert!(SubscriberEmail::try_from(email).is_ok());
+    }
+}
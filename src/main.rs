@@ -1,8 +1,6 @@
-use sqlx::PgPool;
-use std::net::TcpListener;
 use tracing::subscriber::set_global_default;
 use tracing_log::LogTracer;
-use zero2prod::{configuration::get_configuration, startup::run, telemetry::get_subscriber};
+use zero2prod::{configuration::get_configuration, startup::Application, telemetry::get_subscriber};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -12,12 +10,6 @@ async fn main() -> std::io::Result<()> {
     set_global_default(subscriber).expect("Failed to set subscriber");
 
     let configuration = get_configuration().expect("Failed to read configuration");
-    let address = format!("127.0.0.1:{}", configuration.application_port);
-
-    let connection_pool = PgPool::connect(&configuration.database.get_connection_string())
-        .await
-        .expect("Failed to connect to postgres");
-    let listener = TcpListener::bind(address)?;
-
-    run(listener, connection_pool)?.await
+    let application = Application::build(configuration).await?;
+    application.run_until_stopped().await
 }
@@ -1,18 +1,83 @@
+use linkify::{LinkFinder, LinkKind};
 use sqlx::{Connection, Executor, PgConnection, PgPool};
-use std::net::TcpListener;
 use tracing::subscriber::set_global_default;
 use tracing_log::LogTracer;
 use uuid::Uuid;
+use wiremock::matchers::{method, path};
+use wiremock::{Match, Mock, MockServer, Request, ResponseTemplate};
 use zero2prod::{
     configuration::{get_configuration, DatabaseSettings},
-    startup::run,
+    startup::Application,
     telemetry::get_subscriber,
 };
 
+/// Matches a Postmark-style `send_email` request body addressed to
+/// `recipient`, whose HTML body contains a confirmation link.
+struct SendEmailBodyMatcher {
+    recipient: String,
+}
+
+impl Match for SendEmailBodyMatcher {
+    fn matches(&self, request: &Request) -> bool {
+        let Ok(body) = serde_json::from_slice::<serde_json::Value>(&request.body) else {
+            return false;
+        };
+        body["To"].as_str() == Some(self.recipient.as_str())
+            && body["HtmlBody"]
+                .as_str()
+                .map(|html| html.contains("/subscriptions/confirm?subscription_token="))
+                .unwrap_or(false)
+    }
+}
+
 pub struct TestApp {
     pub address: String,
+    pub port: u16,
     pub db_config: DatabaseSettings,
     pub db_pool: PgPool,
+    pub email_server: MockServer,
+}
+
+/// The confirmation link embedded in a confirmation email, in both its
+/// HTML and plain-text form.
+pub struct ConfirmationLinks {
+    pub html: reqwest::Url,
+    pub plain_text: reqwest::Url,
+}
+
+impl TestApp {
+    pub async fn post_subscriptions(&self, body: String) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(&format!("{}/subscriptions", &self.address))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    /// Extracts the confirmation links embedded in a mocked confirmation
+    /// email request, rewriting their port to the one this test's app is
+    /// actually bound to (the app's own `base_url` config doesn't know it).
+    pub fn get_confirmation_links(&self, email_request: &wiremock::Request) -> ConfirmationLinks {
+        let body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+
+        let get_link = |s: &str| {
+            let links: Vec<_> = LinkFinder::new()
+                .links(s)
+                .filter(|l| *l.kind() == LinkKind::Url)
+                .collect();
+            assert_eq!(links.len(), 1);
+            let mut confirmation_link = reqwest::Url::parse(links[0].as_str()).unwrap();
+            assert_eq!(confirmation_link.host_str().unwrap(), "127.0.0.1");
+            confirmation_link.set_port(Some(self.port)).unwrap();
+            confirmation_link
+        };
+
+        let html = get_link(body["HtmlBody"].as_str().unwrap());
+        let plain_text = get_link(body["TextBody"].as_str().unwrap());
+        ConfirmationLinks { html, plain_text }
+    }
 }
 
 // The logger must be initialized only once.
@@ -36,21 +101,32 @@ async fn spawn_app() -> TestApp {
     // We create and use a random db name so that it does not clash with production or other tests
     let mut configuration = get_configuration().expect("Failed to read configuration");
     configuration.database.database_name = Uuid::new_v4().to_string();
+    // Bind to a random OS-assigned port instead of the one from configuration.
+    configuration.application.port = 0;
     let connection_pool = configure_database(&configuration.database).await;
 
-    // Bind the app to a random port.
-    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
-    let port = listener.local_addr().unwrap().port();
-    let address = format!("http://127.0.0.1:{}", port);
+    // Stand in for the real email API so tests can assert on outbound requests
+    // without ever reaching a transactional-email provider.
+    let email_server = MockServer::start().await;
+    configuration.email_client.base_url = email_server.uri();
 
-    // Spawn the app as a background task
-    let server = run(listener, connection_pool.clone()).expect("Failed to bind address");
-    let _ = tokio::spawn(server);
+    let db_config = configuration.database.clone();
+
+    // Build the app through the same path that ships to production, then
+    // spawn it as a background task.
+    let application = Application::build(configuration)
+        .await
+        .expect("Failed to build application");
+    let port = application.port();
+    let address = format!("http://127.0.0.1:{}", port);
+    let _ = tokio::spawn(application.run_until_stopped());
 
     TestApp {
         address,
-        db_config: configuration.database,
+        port,
+        db_config,
         db_pool: connection_pool,
+        email_server,
     }
 }
 
@@ -58,7 +134,7 @@ async fn spawn_app() -> TestApp {
 async fn clean_up(app: TestApp) {
     app.db_pool.close().await;
 
-    let mut connection = PgConnection::connect(&app.db_config.get_connection_string_default_db())
+    let mut connection = PgConnection::connect_with(&app.db_config.without_db())
         .await
         .expect("Failed to connect to Postgres");
 
@@ -72,7 +148,7 @@ async fn clean_up(app: TestApp) {
 }
 
 pub async fn configure_database(config: &DatabaseSettings) -> PgPool {
-    let mut connection = PgConnection::connect(&config.get_connection_string_default_db())
+    let mut connection = PgConnection::connect_with(&config.without_db())
         .await
         .expect("Failed to connect to Postgres");
 
@@ -81,7 +157,7 @@ pub async fn configure_database(config: &DatabaseSettings) -> PgPool {
         .await
         .expect("Failed to create the test database");
 
-    let connection_pool = PgPool::connect(&config.get_connection_string())
+    let connection_pool = PgPool::connect_with(config.with_db())
         .await
         .expect("Failed to connect to Postgres");
 
@@ -113,16 +189,18 @@ async fn health_check_works() {
 #[actix_rt::test]
 async fn subscribe_return_200_with_valid_data() {
     let app = spawn_app().await;
-    let client = reqwest::Client::new();
     let body = "name=le%20guin&email=ursula_le_guin%40gmail.com";
 
-    let response = client
-        .post(&format!("{}/subscriptions", &app.address))
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .body(body)
-        .send()
-        .await
-        .expect("Failed to execute request");
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .and(SendEmailBodyMatcher {
+            recipient: "ursula_le_guin@gmail.com".into(),
+        })
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let response = app.post_subscriptions(body.into()).await;
 
     assert_eq!(200, response.status().as_u16());
 
@@ -140,7 +218,6 @@ async fn subscribe_return_200_with_valid_data() {
 #[actix_rt::test]
 async fn subscribe_return_400_with_missing_data() {
     let app = spawn_app().await;
-    let client = reqwest::Client::new();
     let test_cases = vec![
         ("name=le%20guin", "missing the email"),
         ("email=ursula_le_guin%40gmail.com", "missing the name"),
@@ -148,13 +225,7 @@ async fn subscribe_return_400_with_missing_data() {
     ];
 
     for (invalid_body, error_message) in test_cases {
-        let response = client
-            .post(&format!("{}/subscriptions", &app.address))
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(invalid_body)
-            .send()
-            .await
-            .expect("Failed to execute request");
+        let response = app.post_subscriptions(invalid_body.into()).await;
 
         assert_eq!(
             400,
@@ -166,3 +237,103 @@ async fn subscribe_return_400_with_missing_data() {
 
     clean_up(app).await;
 }
+
+#[actix_rt::test]
+async fn subscribe_sends_a_confirmation_email_for_valid_data() {
+    let app = spawn_app().await;
+    let body = "name=le%20guin&email=ursula_le_guin%40gmail.com";
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .and(SendEmailBodyMatcher {
+            recipient: "ursula_le_guin@gmail.com".into(),
+        })
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(body.into()).await;
+
+    // Mock's `expect(1)` is verified when `app.email_server` is dropped.
+    clean_up(app).await;
+}
+
+#[actix_rt::test]
+async fn subscribe_returns_500_when_the_email_provider_fails() {
+    let app = spawn_app().await;
+    let body = "name=le%20guin&email=ursula_le_guin%40gmail.com";
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&app.email_server)
+        .await;
+
+    let response = app.post_subscriptions(body.into()).await;
+
+    assert_eq!(500, response.status().as_u16());
+
+    clean_up(app).await;
+}
+
+#[actix_rt::test]
+async fn confirmations_without_a_token_are_rejected_with_a_400() {
+    let app = spawn_app().await;
+
+    let response = reqwest::get(&format!("{}/subscriptions/confirm", app.address))
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(400, response.status().as_u16());
+
+    clean_up(app).await;
+}
+
+#[actix_rt::test]
+async fn confirmations_with_an_unknown_token_are_rejected_with_a_401() {
+    let app = spawn_app().await;
+
+    let response = reqwest::get(&format!(
+        "{}/subscriptions/confirm?subscription_token=unknown-token",
+        app.address
+    ))
+    .await
+    .expect("Failed to execute request");
+
+    assert_eq!(401, response.status().as_u16());
+
+    clean_up(app).await;
+}
+
+#[actix_rt::test]
+async fn the_link_returned_by_subscribe_confirms_a_subscriber() {
+    let app = spawn_app().await;
+    let body = "name=le%20guin&email=ursula_le_guin%40gmail.com";
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(body.into()).await;
+
+    let email_request = &app.email_server.received_requests().await.unwrap()[0];
+    let confirmation_links = app.get_confirmation_links(email_request);
+
+    let response = reqwest::get(confirmation_links.html)
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let saved = sqlx::query!("SELECT status FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved subscription");
+
+    assert_eq!(saved.status, "confirmed");
+
+    clean_up(app).await;
+}